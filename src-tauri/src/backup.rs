@@ -0,0 +1,172 @@
+use crate::SaveInfo;
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How many snapshots to keep per game before the oldest ones are pruned.
+const MAX_BACKUPS_PER_GAME: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SaveBackup {
+    pub path: PathBuf,
+    pub unix_ts: u64,
+}
+
+#[derive(Debug)]
+pub enum BackupError {
+    Io(io::Error),
+    Ftp(suppaftp::FtpError),
+}
+
+impl From<io::Error> for BackupError {
+    fn from(err: io::Error) -> Self {
+        BackupError::Io(err)
+    }
+}
+
+impl From<suppaftp::FtpError> for BackupError {
+    fn from(err: suppaftp::FtpError) -> Self {
+        BackupError::Ftp(err)
+    }
+}
+
+fn backup_dir(pocket_path: &Path, save_info: &SaveInfo) -> PathBuf {
+    pocket_path
+        .join(".pocket-sync")
+        .join("backups")
+        .join(save_info.core.to_mister())
+        .join(&save_info.game)
+}
+
+/// Copies `existing_save` into the timestamped backup store for `save_info` before it
+/// gets overwritten, then prunes snapshots beyond `MAX_BACKUPS_PER_GAME`. A no-op if
+/// there's nothing to snapshot yet (first sync for this game).
+pub fn snapshot_file(save_info: &SaveInfo, pocket_path: &Path, existing_save: &Path) -> Result<(), BackupError> {
+    if !existing_save.exists() {
+        return Ok(());
+    }
+
+    let dir = backup_dir(pocket_path, save_info);
+    fs::create_dir_all(&dir)?;
+    fs::copy(existing_save, dir.join(snapshot_file_name()))?;
+
+    prune_backups(&dir)
+}
+
+/// Fetches the current MiSTer save over FTP (if any) and adds it to the backup store
+/// before `use_pocket` overwrites it with `put_file`.
+pub fn snapshot_mister_save(
+    save_info: &SaveInfo,
+    pocket_path: &Path,
+    mister_path: &Path,
+    ftp_stream: &mut suppaftp::FtpStream,
+) -> Result<(), BackupError> {
+    use std::io::Read;
+
+    let parent = mister_path.parent().unwrap().to_str().unwrap();
+    if ftp_stream.cwd(parent).is_err() {
+        // Destination directory doesn't exist yet on the MiSTer side - nothing to back up.
+        return Ok(());
+    }
+
+    let file_name = mister_path.file_name().unwrap().to_str().unwrap();
+    let mut remote_file = match ftp_stream.retr_as_buffer(file_name) {
+        Ok(buffer) => buffer,
+        Err(_) => return Ok(()),
+    };
+
+    let mut bytes = Vec::new();
+    remote_file.read_to_end(&mut bytes)?;
+
+    let dir = backup_dir(pocket_path, save_info);
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(snapshot_file_name()), &bytes)?;
+
+    prune_backups(&dir)
+}
+
+/// Lists the available snapshots for a save, newest first.
+pub fn list_save_backups(save_info: &SaveInfo, pocket_path: &Path) -> Result<Vec<SaveBackup>, BackupError> {
+    let mut backups = read_backups(&backup_dir(pocket_path, save_info))?;
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.unix_ts));
+    Ok(backups)
+}
+
+/// Restores a chosen snapshot back to both the Pocket save path and the MiSTer FTP path.
+pub fn restore_save_backup(
+    backup: &SaveBackup,
+    pocket_save_info: &SaveInfo,
+    mister_path: &Path,
+    pocket_path: &Path,
+    ftp_stream: &mut suppaftp::FtpStream,
+) -> Result<(), BackupError> {
+    let pocket_dest = pocket_path.join(&pocket_save_info.path);
+    if let Some(prefix) = pocket_dest.parent() {
+        fs::create_dir_all(prefix)?;
+    }
+    fs::copy(&backup.path, &pocket_dest)?;
+
+    ftp_stream.cwd(mister_path.parent().unwrap().to_str().unwrap())?;
+    let mut file = File::open(&backup.path)?;
+    let file_name = mister_path.file_name().unwrap().to_str().unwrap();
+    ftp_stream.put_file(file_name, &mut file)?;
+
+    Ok(())
+}
+
+fn read_backups(dir: &Path) -> Result<Vec<SaveBackup>, BackupError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let unix_ts = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.split('-').next())
+            .and_then(|ts| ts.parse::<u64>().ok());
+
+        if let Some(unix_ts) = unix_ts {
+            backups.push(SaveBackup { path, unix_ts });
+        }
+    }
+
+    Ok(backups)
+}
+
+fn prune_backups(dir: &Path) -> Result<(), BackupError> {
+    let mut backups = read_backups(dir)?;
+    if backups.len() <= MAX_BACKUPS_PER_GAME {
+        return Ok(());
+    }
+
+    backups.sort_by_key(|backup| backup.unix_ts);
+    for stale in &backups[..backups.len() - MAX_BACKUPS_PER_GAME] {
+        fs::remove_file(&stale.path)?;
+    }
+
+    Ok(())
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// A process-wide counter appended to snapshot filenames so two snapshots taken within
+/// the same second (e.g. syncing several ROM paths for one game back to back) don't
+/// overwrite each other - `unix_ts` alone only has one-second resolution.
+static SNAPSHOT_SEQ: AtomicU64 = AtomicU64::new(0);
+
+fn snapshot_file_name() -> String {
+    let seq = SNAPSHOT_SEQ.fetch_add(1, Ordering::Relaxed);
+    format!("{}-{}.sav", unix_timestamp(), seq)
+}
@@ -0,0 +1,72 @@
+use crate::backup::{self, SaveBackup};
+use crate::jobs::{run_sync_jobs, SyncSummaryReport};
+use crate::save_compare::{check_save, remove_duplicates};
+use crate::sync_policy::SyncPolicy;
+use crate::{PlatformSave, SaveInfo};
+use std::path::{Path, PathBuf};
+
+/// Connects to the MiSTer's FTP server, which (like the rest of the MiSTer FTP tooling
+/// this app talks to) is set up for anonymous access to the SD card rather than a
+/// per-user account, so there's no real credential to prompt the user for.
+fn connect_mister(mister_host: String) -> Result<suppaftp::FtpStream, String> {
+    let mut ftp_stream = suppaftp::FtpStream::connect(mister_host).map_err(|err| format!("{:?}", err))?;
+    ftp_stream
+        .login("anonymous", "anonymous")
+        .map_err(|err| format!("{:?}", err))?;
+    Ok(ftp_stream)
+}
+
+/// Tauri command: lists the available snapshots for a save, newest first, so the UI
+/// can offer a rollback picker before a sync is kicked off.
+#[tauri::command(async)]
+pub fn list_save_backups(pocket_path: String, save_info: SaveInfo) -> Result<Vec<SaveBackup>, String> {
+    backup::list_save_backups(&save_info, Path::new(&pocket_path)).map_err(|err| format!("{:?}", err))
+}
+
+/// Tauri command: restores a chosen snapshot back to both the Pocket path and the
+/// MiSTer FTP path.
+#[tauri::command(async)]
+pub fn restore_save_backup(
+    backup: SaveBackup,
+    pocket_save_info: SaveInfo,
+    mister_path: String,
+    pocket_path: String,
+    mister_host: String,
+) -> Result<(), String> {
+    let mut ftp_stream = connect_mister(mister_host)?;
+
+    backup::restore_save_backup(
+        &backup,
+        &pocket_save_info,
+        &PathBuf::from(mister_path),
+        Path::new(&pocket_path),
+        &mut ftp_stream,
+    )
+    .map_err(|err| format!("{:?}", err))
+}
+
+/// Tauri command: runs a full batch sync and returns which saves synced, which were
+/// skipped, and why any of them failed, instead of the old all-or-nothing panic.
+#[tauri::command(async)]
+pub fn run_sync(
+    app_handle: tauri::AppHandle,
+    pocket_saves: Vec<PlatformSave>,
+    mister_saves: Vec<PlatformSave>,
+    last_merge: i64,
+    pocket_path: String,
+    mister_host: String,
+) -> Result<SyncSummaryReport, String> {
+    let mut ftp_stream = connect_mister(mister_host)?;
+    let pocket_path = PathBuf::from(pocket_path);
+    let policy = SyncPolicy::load_or_default(&pocket_path.join(".pocket-sync").join("sync-policy.json"));
+
+    let all_saves: Vec<&PlatformSave> = pocket_saves.iter().chain(mister_saves.iter()).collect();
+    let comparisons: Vec<_> = all_saves
+        .into_iter()
+        .map(|save| check_save(save, &pocket_saves, &mister_saves, last_merge, &mut ftp_stream, &policy))
+        .collect();
+    let comparisons = remove_duplicates(comparisons, &policy);
+
+    let summary = run_sync_jobs(comparisons, &mut ftp_stream, &pocket_path, &app_handle);
+    Ok(summary.into_report())
+}
@@ -0,0 +1,45 @@
+use std::fmt;
+
+/// Everything that can go wrong while applying a single `SaveComparison`, replacing the
+/// `unwrap()`/`panic!` calls that used to bring down the whole batch.
+#[derive(Debug)]
+pub enum SyncError {
+    Ftp(suppaftp::FtpError),
+    Io(std::io::Error),
+    NoMatchingRom,
+    NonExistentSave,
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SyncError::Ftp(err) => write!(f, "FTP error: {}", err),
+            SyncError::Io(err) => write!(f, "IO error: {}", err),
+            SyncError::NoMatchingRom => write!(f, "no matching ROM found on the Pocket"),
+            SyncError::NonExistentSave => write!(f, "attempted to use a non-existent save"),
+        }
+    }
+}
+
+impl std::error::Error for SyncError {}
+
+impl From<suppaftp::FtpError> for SyncError {
+    fn from(err: suppaftp::FtpError) -> Self {
+        SyncError::Ftp(err)
+    }
+}
+
+impl From<std::io::Error> for SyncError {
+    fn from(err: std::io::Error) -> Self {
+        SyncError::Io(err)
+    }
+}
+
+impl From<crate::backup::BackupError> for SyncError {
+    fn from(err: crate::backup::BackupError) -> Self {
+        match err {
+            crate::backup::BackupError::Io(err) => SyncError::Io(err),
+            crate::backup::BackupError::Ftp(err) => SyncError::Ftp(err),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{
+    errors::SyncError,
+    progress::{ProgressSink, SyncContext, SyncDirection, SyncPhase},
+    save_compare::SaveComparison,
+    SaveInfo,
+};
+use std::path::Path;
+
+/// What happened when a single `SaveComparison` was run as part of a batch.
+#[derive(Debug)]
+pub enum JobOutcome {
+    Synced,
+    Skipped(&'static str),
+    Failed(SyncError),
+}
+
+pub struct JobResult<'a> {
+    pub game: &'a SaveInfo,
+    pub outcome: JobOutcome,
+}
+
+#[derive(Default)]
+pub struct SyncSummary<'a> {
+    pub succeeded: Vec<&'a SaveInfo>,
+    pub skipped: Vec<(&'a SaveInfo, &'static str)>,
+    pub failed: Vec<(&'a SaveInfo, SyncError)>,
+}
+
+/// Runs every comparison as an independent unit: a failure on one game (a missing ROM,
+/// a dropped FTP connection, an unreadable file) is recorded and the runner moves on to
+/// the next save instead of unwinding the whole batch.
+pub fn run_sync_jobs<'a>(
+    comparisons: Vec<SaveComparison<'a>>,
+    ftp_stream: &mut suppaftp::FtpStream,
+    pocket_path: &Path,
+    progress: &dyn ProgressSink,
+) -> SyncSummary<'a> {
+    let mut summary = SyncSummary::default();
+    let total = comparisons.len();
+
+    for (index, comparison) in comparisons.into_iter().enumerate() {
+        let result = run_job(&comparison, ftp_stream, pocket_path, total, index, progress);
+
+        let Some(result) = result else {
+            continue;
+        };
+
+        match result.outcome {
+            JobOutcome::Synced => summary.succeeded.push(result.game),
+            JobOutcome::Skipped(reason) => summary.skipped.push((result.game, reason)),
+            JobOutcome::Failed(err) => summary.failed.push((result.game, err)),
+        }
+    }
+
+    summary
+}
+
+fn run_job<'a>(
+    comparison: &SaveComparison<'a>,
+    ftp_stream: &mut suppaftp::FtpStream,
+    pocket_path: &Path,
+    total: usize,
+    index: usize,
+    progress: &dyn ProgressSink,
+) -> Option<JobResult<'a>> {
+    let make_ctx = |game: &SaveInfo, direction: SyncDirection| SyncContext {
+        total,
+        index,
+        game: game.game.clone(),
+        direction,
+        sink: progress,
+    };
+
+    match comparison {
+        SaveComparison::NoSyncNeeded => None,
+        SaveComparison::PocketOnly(save_info) => {
+            let ctx = make_ctx(save_info, SyncDirection::PocketToMister);
+            ctx.emit(SyncPhase::Comparing, 0);
+            Some(JobResult {
+                game: save_info,
+                outcome: to_outcome(comparison.use_pocket(ftp_stream, pocket_path, &ctx)),
+            })
+        }
+        SaveComparison::MiSTerOnly(save_info) => {
+            let ctx = make_ctx(save_info, SyncDirection::MiSTerToPocket);
+            ctx.emit(SyncPhase::Comparing, 0);
+            Some(JobResult {
+                game: save_info,
+                outcome: to_outcome(comparison.use_mister(ftp_stream, pocket_path, &ctx)),
+            })
+        }
+        SaveComparison::PocketNewer(save_pair) => {
+            let game = save_pair.newer_save();
+            let ctx = make_ctx(game, SyncDirection::PocketToMister);
+            ctx.emit(SyncPhase::Comparing, 0);
+            Some(JobResult {
+                game,
+                outcome: to_outcome(comparison.use_pocket(ftp_stream, pocket_path, &ctx)),
+            })
+        }
+        SaveComparison::MiSTerNewer(save_pair) => {
+            let game = save_pair.newer_save();
+            let ctx = make_ctx(game, SyncDirection::MiSTerToPocket);
+            ctx.emit(SyncPhase::Comparing, 0);
+            Some(JobResult {
+                game,
+                outcome: to_outcome(comparison.use_mister(ftp_stream, pocket_path, &ctx)),
+            })
+        }
+        SaveComparison::Conflict(save_pair) => Some(JobResult {
+            game: save_pair.newer_save(),
+            outcome: JobOutcome::Skipped("conflicting edits on both sides - resolve manually"),
+        }),
+    }
+}
+
+fn to_outcome(result: Result<(), SyncError>) -> JobOutcome {
+    match result {
+        Ok(()) => JobOutcome::Synced,
+        Err(err) => JobOutcome::Failed(err),
+    }
+}
+
+/// Owned, serializable version of `SyncSummary` for handing back across the Tauri bridge.
+#[derive(Debug, serde::Serialize)]
+pub struct SyncSummaryReport {
+    pub succeeded: Vec<SaveInfo>,
+    pub skipped: Vec<(SaveInfo, String)>,
+    pub failed: Vec<(SaveInfo, String)>,
+}
+
+impl SyncSummary<'_> {
+    pub fn into_report(self) -> SyncSummaryReport {
+        SyncSummaryReport {
+            succeeded: self.succeeded.into_iter().cloned().collect(),
+            skipped: self
+                .skipped
+                .into_iter()
+                .map(|(save, reason)| (save.clone(), reason.to_string()))
+                .collect(),
+            failed: self
+                .failed
+                .into_iter()
+                .map(|(save, err)| (save.clone(), err.to_string()))
+                .collect(),
+        }
+    }
+}
@@ -9,6 +9,14 @@ use std::path::PathBuf;
 use std::sync::Mutex;
 use tauri::api::dialog;
 
+mod backup;
+mod commands;
+mod errors;
+mod jobs;
+mod progress;
+mod save_compare;
+mod sync_policy;
+
 struct PocketSyncState(Mutex<PathBuf>);
 
 // Learn more about Tauri commands at https://tauri.app/v1/guides/features/command
@@ -79,7 +87,10 @@ fn main() {
             list_files,
             read_binary_file,
             read_text_file,
-            save_file
+            save_file,
+            commands::list_save_backups,
+            commands::restore_save_backup,
+            commands::run_sync,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
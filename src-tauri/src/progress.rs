@@ -0,0 +1,73 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SyncDirection {
+    PocketToMister,
+    MiSTerToPocket,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SyncPhase {
+    Comparing,
+    Transferring,
+    Done,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncProgressEvent {
+    pub total: usize,
+    pub index: usize,
+    pub game: String,
+    pub direction: SyncDirection,
+    pub phase: SyncPhase,
+    pub bytes_transferred: u64,
+}
+
+/// Sink for progress events emitted while a batch sync runs. The Tauri command
+/// implementation emits through the app handle's event system; anything else (tests,
+/// a future CLI) can just drain an `mpsc::Sender`.
+pub trait ProgressSink {
+    fn emit(&self, event: SyncProgressEvent);
+}
+
+impl ProgressSink for std::sync::mpsc::Sender<SyncProgressEvent> {
+    fn emit(&self, event: SyncProgressEvent) {
+        let _ = self.send(event);
+    }
+}
+
+/// A no-op sink for callers that don't care about progress.
+pub struct NullProgressSink;
+
+impl ProgressSink for NullProgressSink {
+    fn emit(&self, _event: SyncProgressEvent) {}
+}
+
+/// Emits progress straight to the frontend as `sync-progress` events.
+impl ProgressSink for tauri::AppHandle {
+    fn emit(&self, event: SyncProgressEvent) {
+        let _ = tauri::Manager::emit_all(self, "sync-progress", event);
+    }
+}
+
+/// Everything a running job needs to know to report where it's at.
+pub struct SyncContext<'a> {
+    pub total: usize,
+    pub index: usize,
+    pub game: String,
+    pub direction: SyncDirection,
+    pub sink: &'a dyn ProgressSink,
+}
+
+impl SyncContext<'_> {
+    pub fn emit(&self, phase: SyncPhase, bytes_transferred: u64) {
+        self.sink.emit(SyncProgressEvent {
+            total: self.total,
+            index: self.index,
+            game: self.game.clone(),
+            direction: self.direction,
+            phase,
+            bytes_transferred,
+        });
+    }
+}
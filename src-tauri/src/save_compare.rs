@@ -0,0 +1,433 @@
+use crate::{
+    cores::TransformCore,
+    errors::SyncError,
+    pocket_files::{convert_rom_path_to_save_path, find_roms_for_save},
+    progress::{SyncContext, SyncPhase},
+    sync_policy::SyncPolicy,
+    PlatformSave, SaveInfo,
+};
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SavePair<'a> {
+    pocket: &'a SaveInfo,
+    mister: &'a SaveInfo,
+}
+
+impl<'a> SavePair<'a> {
+    pub fn is_pocket_newer(&self) -> bool {
+        self.pocket.date_modified > self.mister.date_modified
+    }
+
+    pub fn newer_save(&self) -> &'a SaveInfo {
+        if self.pocket.date_modified > self.mister.date_modified {
+            self.pocket
+        } else {
+            self.mister
+        }
+    }
+
+    pub fn older_save(&self) -> &'a SaveInfo {
+        if self.pocket.date_modified > self.mister.date_modified {
+            self.mister
+        } else {
+            self.pocket
+        }
+    }
+}
+
+impl<'a> fmt::Display for SavePair<'a> {
+    // This trait requires `fmt` with this exact signature.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let titles = match self.is_pocket_newer() {
+            true => ("-- Pocket (newer)", "-- MiSTer (older)"),
+            false => ("-- MiSTer (newer)", "-- Pocket (older)"),
+        };
+
+        write!(
+            f,
+            "{}\n{} \n\n--- VS ---\n\n{}\n{}",
+            titles.0,
+            self.newer_save(),
+            titles.1,
+            self.older_save()
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SaveComparison<'a> {
+    PocketOnly(&'a SaveInfo),
+    MiSTerOnly(&'a SaveInfo),
+    PocketNewer(SavePair<'a>),
+    MiSTerNewer(SavePair<'a>),
+    Conflict(SavePair<'a>),
+    NoSyncNeeded,
+}
+
+impl<'a> SaveComparison<'a> {
+    /// The save this comparison is about, for policy filtering and reporting. `None`
+    /// for `NoSyncNeeded`, which isn't about any one save.
+    pub fn representative_save(&self) -> Option<&'a SaveInfo> {
+        match self {
+            SaveComparison::PocketOnly(save_info) | SaveComparison::MiSTerOnly(save_info) => {
+                Some(save_info)
+            }
+            SaveComparison::PocketNewer(save_pair)
+            | SaveComparison::MiSTerNewer(save_pair)
+            | SaveComparison::Conflict(save_pair) => Some(save_pair.newer_save()),
+            SaveComparison::NoSyncNeeded => None,
+        }
+    }
+
+    pub fn use_mister(
+        &self,
+        ftp_stream: &mut suppaftp::FtpStream,
+        pocket_path: &Path,
+        ctx: &SyncContext,
+    ) -> Result<(), SyncError> {
+        let mister_save_info = match self {
+            SaveComparison::MiSTerOnly(save_info) => &save_info,
+            Self::PocketNewer(save_pair)
+            | Self::MiSTerNewer(save_pair)
+            | Self::Conflict(save_pair) => &save_pair.mister,
+            _ => return Err(SyncError::NonExistentSave),
+        };
+        let path = &mister_save_info.path;
+        let file_name = path.file_name().unwrap();
+
+        let pocket_save_paths: Vec<PathBuf> = match self {
+            SaveComparison::MiSTerOnly(save_info) => {
+                let found = find_roms_for_save(
+                    &save_info.game,
+                    &save_info.core.rom_filetypes(),
+                    pocket_path,
+                )
+                .iter()
+                .map(|p| convert_rom_path_to_save_path(p))
+                .collect();
+
+                found
+            }
+            Self::PocketNewer(save_pair)
+            | Self::MiSTerNewer(save_pair)
+            | Self::Conflict(save_pair) => vec![pocket_path.join(save_pair.pocket.path.clone())],
+            Self::PocketOnly(_) | Self::NoSyncNeeded => return Err(SyncError::NonExistentSave),
+        };
+
+        if pocket_save_paths.len() == 0 {
+            return Err(SyncError::NoMatchingRom);
+        }
+
+        // Stream into a `.pocket-sync-tmp` file next to each destination rather than truncating the
+        // real save up front, so a dropped connection or a write failure partway through
+        // the transfer leaves the existing Pocket save untouched instead of corrupted.
+        let mut dest_files: Vec<(PathBuf, File)> = Vec::with_capacity(pocket_save_paths.len());
+        for pocket_save_path in &pocket_save_paths {
+            let prefix = pocket_save_path.parent().unwrap();
+            std::fs::create_dir_all(prefix)?;
+
+            crate::backup::snapshot_file(mister_save_info, pocket_path, pocket_save_path)?;
+
+            let tmp_path = pocket_save_path.with_extension("pocket-sync-tmp");
+            dest_files.push((tmp_path.clone(), File::create(&tmp_path)?));
+        }
+
+        ftp_stream.cwd(path.parent().unwrap().to_path_buf().to_str().unwrap())?;
+
+        let mut bytes_transferred: u64 = 0;
+        // `retr`'s callback has to return an `FtpError`, but a failure to write to local
+        // disk isn't an FTP problem - stash it here and re-raise as `SyncError::Io` below
+        // so the per-game report doesn't blame a full Pocket disk on the FTP connection.
+        let mut local_write_error: Option<std::io::Error> = None;
+        let retr_result = ftp_stream.retr(file_name.to_str().unwrap(), |reader| {
+            let mut chunk = [0u8; 8192];
+            loop {
+                let read = reader
+                    .read(&mut chunk)
+                    .map_err(suppaftp::FtpError::ConnectionError)?;
+                if read == 0 {
+                    break;
+                }
+
+                for (_, file) in dest_files.iter_mut() {
+                    if let Err(err) = file.write_all(&chunk[..read]) {
+                        local_write_error = Some(err);
+                        return Err(suppaftp::FtpError::ConnectionError(std::io::Error::new(
+                            std::io::ErrorKind::Other,
+                            "local write failed, see local_write_error",
+                        )));
+                    }
+                }
+
+                bytes_transferred += read as u64;
+                ctx.emit(SyncPhase::Transferring, bytes_transferred);
+            }
+
+            Ok(())
+        });
+
+        if local_write_error.is_some() || retr_result.is_err() {
+            for (tmp_path, _) in &dest_files {
+                let _ = std::fs::remove_file(tmp_path);
+            }
+        }
+
+        if let Some(err) = local_write_error {
+            return Err(SyncError::Io(err));
+        }
+        retr_result?;
+
+        // Drop the open handles before renaming the temp files into place.
+        let tmp_paths: Vec<PathBuf> = dest_files.into_iter().map(|(tmp_path, _)| tmp_path).collect();
+        for (tmp_path, pocket_save_path) in tmp_paths.iter().zip(&pocket_save_paths) {
+            fs::rename(tmp_path, pocket_save_path)?;
+        }
+
+        ctx.emit(SyncPhase::Done, bytes_transferred);
+
+        return Ok(());
+    }
+
+    pub fn use_pocket(
+        &self,
+        ftp_stream: &mut suppaftp::FtpStream,
+        pocket_path: &Path,
+        ctx: &SyncContext,
+    ) -> Result<(), SyncError> {
+        let pocket_save_info = match self {
+            SaveComparison::PocketOnly(save_info) => &save_info,
+            Self::PocketNewer(save_pair)
+            | Self::MiSTerNewer(save_pair)
+            | Self::Conflict(save_pair) => &save_pair.pocket,
+            _ => return Err(SyncError::NonExistentSave),
+        };
+        let path = &pocket_save_info.path;
+        let file_name = path.file_name().unwrap();
+        let mister_save_path = match self {
+            SaveComparison::PocketOnly(save_info) => {
+                pocket_path.join(format!("/media/fat/saves/{}", save_info.core.to_mister()))
+            }
+            Self::PocketNewer(save_pair)
+            | Self::MiSTerNewer(save_pair)
+            | Self::Conflict(save_pair) => save_pair
+                .mister
+                .path
+                .clone()
+                .parent()
+                .unwrap()
+                .to_path_buf(),
+            Self::MiSTerOnly(_) | Self::NoSyncNeeded => return Err(SyncError::NonExistentSave),
+        };
+
+        let mut file = File::open(path)?;
+
+        let mister_path_buf = &mister_save_path.to_path_buf();
+        let mister_path = mister_path_buf.to_str().unwrap();
+        let mister_full_path = mister_save_path.join(file_name);
+
+        crate::backup::snapshot_mister_save(
+            pocket_save_info,
+            pocket_path,
+            &mister_full_path,
+            ftp_stream,
+        )?;
+
+        let file_size = file.metadata()?.len();
+
+        ftp_stream.cwd(mister_path)?;
+        ftp_stream.put_file(file_name.to_str().unwrap(), &mut file)?;
+
+        ctx.emit(SyncPhase::Transferring, file_size);
+        ctx.emit(SyncPhase::Done, file_size);
+
+        return Ok(());
+    }
+}
+
+pub fn check_save<'a>(
+    save: &'a PlatformSave,
+    pocket_saves: &'a Vec<PlatformSave>,
+    mister_saves: &'a Vec<PlatformSave>,
+    last_merge: i64,
+    ftp_stream: &mut suppaftp::FtpStream,
+    policy: &SyncPolicy,
+) -> SaveComparison<'a> {
+    match save {
+        PlatformSave::PocketSave(pocket_save_info) => {
+            if !policy.allows(pocket_save_info) {
+                return SaveComparison::NoSyncNeeded;
+            }
+            if let Some(mister_save_info) =
+                find_matching_mister_save(pocket_save_info, mister_saves)
+            {
+                return get_comparison(pocket_save_info, mister_save_info, last_merge, ftp_stream, policy);
+            } else {
+                return SaveComparison::PocketOnly(pocket_save_info);
+            }
+        }
+        PlatformSave::MiSTerSave(mister_save_info) => {
+            if !policy.allows(mister_save_info) {
+                return SaveComparison::NoSyncNeeded;
+            }
+            if let Some(pocket_save_info) =
+                find_matching_pocket_save(mister_save_info, pocket_saves)
+            {
+                return get_comparison(pocket_save_info, mister_save_info, last_merge, ftp_stream, policy);
+            } else {
+                return SaveComparison::MiSTerOnly(mister_save_info);
+            }
+        }
+    }
+}
+
+fn get_comparison<'a>(
+    pocket_save_info: &'a SaveInfo,
+    mister_save_info: &'a SaveInfo,
+    last_merge: i64,
+    ftp_stream: &mut suppaftp::FtpStream,
+    policy: &SyncPolicy,
+) -> SaveComparison<'a> {
+    if mister_save_info.date_modified < 86400 {
+        let save_pair = SavePair {
+            pocket: pocket_save_info,
+            mister: mister_save_info,
+        };
+
+        // MiSTer save was updated while the RTC wasn't running - raise as a conflict to
+        // be safe, regardless of whether the contents match, unless the policy says to
+        // defer to the configured winner anyway.
+        return if policy.rtc_guard_forces_conflict {
+            SaveComparison::Conflict(save_pair)
+        } else {
+            policy.resolve_conflict(save_pair)
+        };
+    }
+
+    if pocket_save_info.date_modified < last_merge && mister_save_info.date_modified < last_merge {
+        return SaveComparison::NoSyncNeeded;
+    }
+
+    // The date heuristic alone can't tell a real conflict from the same bytes being
+    // touched twice (e.g. the emulator rewriting the save on load), so only once we
+    // know it's ambiguous do we pay for reading and comparing both payloads.
+    if saves_are_identical(pocket_save_info, mister_save_info, ftp_stream) {
+        return SaveComparison::NoSyncNeeded;
+    }
+
+    if pocket_save_info.date_modified > last_merge && mister_save_info.date_modified > last_merge {
+        return policy.resolve_conflict(SavePair {
+            pocket: pocket_save_info,
+            mister: mister_save_info,
+        });
+    }
+
+    if mister_save_info.date_modified > pocket_save_info.date_modified {
+        return SaveComparison::MiSTerNewer(SavePair {
+            pocket: pocket_save_info,
+            mister: &mister_save_info,
+        });
+    } else {
+        return SaveComparison::PocketNewer(SavePair {
+            pocket: pocket_save_info,
+            mister: mister_save_info,
+        });
+    }
+}
+
+/// Reads the Pocket save from disk and the MiSTer save over FTP and compares their
+/// contents byte-for-byte. Any failure to read either side (missing file, FTP error) is
+/// treated as "can't tell", so the caller falls back to the date-based heuristic.
+fn saves_are_identical(
+    pocket_save_info: &SaveInfo,
+    mister_save_info: &SaveInfo,
+    ftp_stream: &mut suppaftp::FtpStream,
+) -> bool {
+    let pocket_bytes = match fs::read(&pocket_save_info.path) {
+        Ok(bytes) => bytes,
+        Err(_) => return false,
+    };
+
+    let mister_bytes = match fetch_mister_save_bytes(mister_save_info, ftp_stream) {
+        Ok(bytes) => bytes,
+        Err(_) => return false,
+    };
+
+    // Both sides are already fully buffered in memory, so a direct comparison is just as
+    // correct as hashing first and avoids pulling in an extra crate for it.
+    pocket_bytes == mister_bytes
+}
+
+fn fetch_mister_save_bytes(
+    mister_save_info: &SaveInfo,
+    ftp_stream: &mut suppaftp::FtpStream,
+) -> Result<Vec<u8>, SyncError> {
+    let path = &mister_save_info.path;
+    ftp_stream.cwd(path.parent().unwrap().to_path_buf().to_str().unwrap())?;
+    let file_name = path.file_name().unwrap().to_str().unwrap();
+    let mut save_file = ftp_stream.retr_as_buffer(file_name)?;
+
+    let mut buf: Vec<u8> = Vec::new();
+    save_file.read_to_end(&mut buf)?;
+
+    Ok(buf)
+}
+
+fn find_matching_mister_save<'a>(
+    save: &SaveInfo,
+    saves: &'a Vec<PlatformSave>,
+) -> Option<&'a SaveInfo> {
+    for mister_save in saves {
+        if let PlatformSave::MiSTerSave(mister_save) = mister_save {
+            if mister_save.core == save.core && mister_save.game == save.game {
+                return Some(&mister_save);
+            }
+        }
+    }
+    return None;
+}
+
+fn find_matching_pocket_save<'a>(
+    save: &SaveInfo,
+    saves: &'a Vec<PlatformSave>,
+) -> Option<&'a SaveInfo> {
+    for pocket_save in saves {
+        if let PlatformSave::PocketSave(pocket_save) = pocket_save {
+            if pocket_save.core == save.core && pocket_save.game == save.game {
+                return Some(&pocket_save);
+            }
+        }
+    }
+    return None;
+}
+
+pub fn remove_duplicates<'a>(
+    save_comparisons: Vec<SaveComparison<'a>>,
+    policy: &SyncPolicy,
+) -> Vec<SaveComparison<'a>> {
+    let mut singles: Vec<SaveComparison> = Vec::new();
+
+    for save_comparison in save_comparisons {
+        if let Some(save_info) = save_comparison.representative_save() {
+            if !policy.allows(save_info) {
+                continue;
+            }
+        }
+
+        match &save_comparison {
+            SaveComparison::NoSyncNeeded => singles.push(save_comparison),
+            _ => {
+                if !singles.contains(&save_comparison) {
+                    singles.push(save_comparison)
+                }
+            }
+        }
+    }
+
+    singles
+}
@@ -0,0 +1,76 @@
+use crate::save_compare::{SaveComparison, SavePair};
+use crate::{cores::TransformCore, SaveInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Which side wins a conflict (both sides touched since the last merge) when the policy
+/// doesn't defer to manual resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictWinner {
+    AlwaysPocket,
+    AlwaysMister,
+    AlwaysNewer,
+    AlwaysAsk,
+}
+
+/// User-configurable rules for how `check_save` turns two saves into a `SaveComparison`.
+/// Read from `.pocket-sync/sync-policy.json` under the Pocket path each time a batch
+/// sync starts (not once at app startup), so users can, for example, declare that a
+/// homebrew core always syncs Pocket -> MiSTer and never prompts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncPolicy {
+    pub conflict_winner: ConflictWinner,
+    pub excluded_cores: HashSet<String>,
+    pub excluded_games: HashSet<String>,
+    /// When true (the default), a MiSTer save timestamped before the RTC settles
+    /// (`date_modified < 86400`) is always a hard conflict. When false, it instead
+    /// defers to `conflict_winner` like any other conflict.
+    pub rtc_guard_forces_conflict: bool,
+}
+
+impl Default for SyncPolicy {
+    fn default() -> Self {
+        SyncPolicy {
+            conflict_winner: ConflictWinner::AlwaysAsk,
+            excluded_cores: HashSet::new(),
+            excluded_games: HashSet::new(),
+            rtc_guard_forces_conflict: true,
+        }
+    }
+}
+
+impl SyncPolicy {
+    /// Loads the policy from `config_path`, falling back to defaults if it's missing or
+    /// can't be parsed.
+    pub fn load_or_default(config_path: &Path) -> Self {
+        std::fs::read_to_string(config_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Whether `save_info` should be considered for syncing at all.
+    pub fn allows(&self, save_info: &SaveInfo) -> bool {
+        !self.excluded_cores.contains(&save_info.core.to_mister())
+            && !self.excluded_games.contains(&save_info.game)
+    }
+
+    /// Turns a conflicting `SavePair` into the comparison the policy calls for.
+    pub fn resolve_conflict<'a>(&self, save_pair: SavePair<'a>) -> SaveComparison<'a> {
+        match self.conflict_winner {
+            ConflictWinner::AlwaysAsk => SaveComparison::Conflict(save_pair),
+            ConflictWinner::AlwaysPocket => SaveComparison::PocketNewer(save_pair),
+            ConflictWinner::AlwaysMister => SaveComparison::MiSTerNewer(save_pair),
+            ConflictWinner::AlwaysNewer => {
+                if save_pair.is_pocket_newer() {
+                    SaveComparison::PocketNewer(save_pair)
+                } else {
+                    SaveComparison::MiSTerNewer(save_pair)
+                }
+            }
+        }
+    }
+}